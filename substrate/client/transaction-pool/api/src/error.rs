@@ -18,6 +18,8 @@
 
 //! Transaction pool errors.
 
+use std::time::{Duration, Instant};
+
 use sp_runtime::transaction_validity::{
 	InvalidTransaction, TransactionPriority as Priority, UnknownTransaction,
 };
@@ -43,7 +45,10 @@ pub enum Error {
 	NoTagsProvided,
 
 	#[error("Transaction temporarily Banned")]
-	TemporarilyBanned,
+	TemporarilyBanned {
+		/// The point in time at which the ban lifts.
+		until: Instant,
+	},
 
 	#[error("[{0:?}] Already imported")]
 	AlreadyImported(Box<dyn std::any::Any + Send + Sync>),
@@ -71,30 +76,300 @@ pub enum Error {
 	RejectedFutureTransaction,
 }
 
+/// Whether an [`Error`] is worth re-submitting to the pool, and why.
+///
+/// This is a richer classification than a plain boolean: it keeps around the
+/// [`Reason`] the verdict was reached for, so a caller (the RPC layer, an
+/// automated resubmitter, ...) can pick an appropriate retry policy instead of
+/// only learning that "it might work again".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recoverability {
+	/// The error is expected to clear on its own, or after the caller adjusts
+	/// the transaction (e.g. bumps the nonce or the fee) and resubmits it.
+	Recoverable(Reason),
+	/// Resubmitting the transaction is not expected to succeed: it will never become
+	/// valid, it is already in the pool, it is outranked by a higher-priority
+	/// transaction, the node can neither propagate nor author it, or it failed with an
+	/// opaque `Custom` code the pool cannot judge.
+	Unrecoverable(Reason),
+}
+
+/// The concrete reason behind a [`Recoverability`] verdict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Reason {
+	/// The sender is temporarily banned; it will be accepted again once the ban lifts.
+	TemporarilyBanned,
+	/// The pool was full when the transaction arrived; there may be space for it later.
+	ImmediatelyDropped,
+	/// The block id used to validate the transaction is not yet known to the pool.
+	///
+	/// The node might be lagging behind, or warp syncing.
+	InvalidBlockId,
+	/// The pool is currently configured to reject transactions only valid in the future.
+	RejectedFutureTransaction,
+	/// A transaction already occupying the sender's priority slot has higher priority.
+	TooLowPriority,
+	/// The transaction is valid for a future nonce.
+	///
+	/// It will become valid once prior transactions from the same sender are included.
+	Future,
+	/// The transaction's nonce has already been consumed.
+	Stale,
+	/// The sender cannot presently pay for the transaction.
+	Payment,
+	/// Including the transaction would exceed a resource limit of the block.
+	///
+	/// It may fit in a future block.
+	ExhaustsResources,
+	/// The call target could not be looked up.
+	///
+	/// This may resolve once the runtime or its state catches up.
+	CannotLookup,
+	/// No unsigned transaction validator recognised the transaction.
+	NoUnsignedValidator,
+	/// A runtime-defined, opaque validity code.
+	///
+	/// The pool has no way to know whether this is transient, so it is treated
+	/// conservatively.
+	Custom(u8),
+	/// The transaction is already known to the pool.
+	AlreadyImported,
+	/// The transaction provides no "provides" tags, so the pool can't identify it.
+	NoTagsProvided,
+	/// The transaction has a cyclic dependency with another queued transaction.
+	CycleDetected,
+	/// The transaction's proof is invalid and will never become valid.
+	BadProof,
+	/// The call itself is invalid and will never become valid.
+	BadCall,
+	/// The transaction's mortality era has already expired.
+	AncientBirthBlock,
+	/// A mandatory extrinsic failed to dispatch.
+	BadMandatory,
+	/// A transaction marked mandatory failed validation.
+	MandatoryValidation,
+	/// The transaction's signer is invalid.
+	BadSigner,
+	/// The transaction cannot be propagated and the local node does not author blocks.
+	Unactionable,
+}
+
+impl std::fmt::Display for Reason {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Reason::TemporarilyBanned => write!(f, "sender is temporarily banned, retry after the ban lifts"),
+			Reason::ImmediatelyDropped => write!(f, "pool was full, retry once space frees up"),
+			Reason::InvalidBlockId => write!(f, "block id unknown to the pool, retry once the node catches up"),
+			Reason::RejectedFutureTransaction =>
+				write!(f, "pool does not accept future transactions right now"),
+			Reason::TooLowPriority => write!(f, "priority too low compared to the pool's occupants"),
+			Reason::Future => write!(f, "valid for a future nonce, retry once prior transactions land"),
+			Reason::Stale => write!(f, "nonce already consumed"),
+			Reason::Payment => write!(f, "sender cannot presently pay, retry once funds are available"),
+			Reason::ExhaustsResources =>
+				write!(f, "would exceed a block resource limit, retry in a later block"),
+			Reason::CannotLookup => write!(f, "call target could not be looked up"),
+			Reason::NoUnsignedValidator => write!(f, "no unsigned validator recognised the transaction"),
+			Reason::Custom(code) => write!(f, "runtime-defined validity code {code}"),
+			Reason::AlreadyImported => write!(f, "transaction is already in the pool"),
+			Reason::NoTagsProvided => write!(f, "transaction provides no tags, pool can't identify it"),
+			Reason::CycleDetected => write!(f, "transaction has a cyclic dependency"),
+			Reason::BadProof => write!(f, "transaction proof is invalid"),
+			Reason::BadCall => write!(f, "the call is invalid"),
+			Reason::AncientBirthBlock => write!(f, "transaction's mortality era has already expired"),
+			Reason::BadMandatory => write!(f, "mandatory extrinsic failed to dispatch"),
+			Reason::MandatoryValidation => write!(f, "transaction marked mandatory failed validation"),
+			Reason::BadSigner => write!(f, "transaction signer is invalid"),
+			Reason::Unactionable => write!(f, "node does not author blocks and can't propagate it"),
+		}
+	}
+}
+
 impl Error {
+	/// Classify this error as recoverable or unrecoverable, with the [`Reason`] why.
+	///
+	/// This cracks open the wrapped `InvalidTransaction`/`UnknownTransaction` so that
+	/// nonce/fee-related validity errors (which may clear once the sender adjusts the
+	/// transaction) are distinguished from permanent ones (a bad proof, a cyclic
+	/// dependency, ...).
+	pub fn recoverability(&self) -> Recoverability {
+		use Recoverability::{Recoverable, Unrecoverable};
+		match self {
+			Error::TemporarilyBanned { .. } => Recoverable(Reason::TemporarilyBanned),
+			Error::ImmediatelyDropped => Recoverable(Reason::ImmediatelyDropped),
+			Error::InvalidBlockId(_) => Recoverable(Reason::InvalidBlockId),
+			Error::RejectedFutureTransaction => Recoverable(Reason::RejectedFutureTransaction),
+			// Kept unrecoverable to preserve `is_retriable`'s pre-existing semantics;
+			// the request's own recoverable list never mentions `TooLowPriority`.
+			Error::TooLowPriority { .. } => Unrecoverable(Reason::TooLowPriority),
+			Error::AlreadyImported(_) => Unrecoverable(Reason::AlreadyImported),
+			Error::NoTagsProvided => Unrecoverable(Reason::NoTagsProvided),
+			Error::CycleDetected => Unrecoverable(Reason::CycleDetected),
+			Error::Unactionable => Unrecoverable(Reason::Unactionable),
+			Error::InvalidTransaction(invalid) => match invalid {
+				InvalidTransaction::Future => Recoverable(Reason::Future),
+				InvalidTransaction::Stale => Recoverable(Reason::Stale),
+				InvalidTransaction::Payment => Recoverable(Reason::Payment),
+				InvalidTransaction::ExhaustsResources => Recoverable(Reason::ExhaustsResources),
+				InvalidTransaction::BadProof => Unrecoverable(Reason::BadProof),
+				InvalidTransaction::Call => Unrecoverable(Reason::BadCall),
+				InvalidTransaction::AncientBirthBlock => Unrecoverable(Reason::AncientBirthBlock),
+				InvalidTransaction::BadMandatory => Unrecoverable(Reason::BadMandatory),
+				InvalidTransaction::MandatoryValidation => Unrecoverable(Reason::MandatoryValidation),
+				InvalidTransaction::BadSigner => Unrecoverable(Reason::BadSigner),
+				InvalidTransaction::Custom(code) => Unrecoverable(Reason::Custom(*code)),
+			},
+			Error::UnknownTransaction(unknown) => match unknown {
+				UnknownTransaction::CannotLookup => Recoverable(Reason::CannotLookup),
+				UnknownTransaction::NoUnsignedValidator => Recoverable(Reason::NoUnsignedValidator),
+				UnknownTransaction::Custom(code) => Unrecoverable(Reason::Custom(*code)),
+			},
+		}
+	}
+
 	/// Returns true if the transaction could be re-submitted to the pool in the future.
 	///
 	/// For example, `Error::ImmediatelyDropped` is retriable, because the transaction
 	/// may enter the pool if there is space for it in the future.
 	pub fn is_retriable(&self) -> bool {
+		matches!(self.recoverability(), Recoverability::Recoverable(_))
+	}
+
+	/// The default wait suggested to a resubmitting client for recoverable errors that
+	/// don't carry a precise deadline of their own.
+	const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+	/// How long a caller should wait before resubmitting this transaction.
+	///
+	/// Returns the exact remaining time for [`Error::TemporarilyBanned`], a sensible
+	/// default for other recoverable errors, and `None` when the error is
+	/// unrecoverable and resubmitting would be pointless.
+	pub fn retry_after(&self) -> Option<Duration> {
+		if let Error::TemporarilyBanned { until } = self {
+			return Some(until.saturating_duration_since(Instant::now()))
+		}
+
+		match self.recoverability() {
+			Recoverability::Recoverable(_) => Some(Self::DEFAULT_RETRY_AFTER),
+			Recoverability::Unrecoverable(_) => None,
+		}
+	}
+
+	/// A stable, numeric RPC error code for this error.
+	///
+	/// These back `transactionWatch`-style RPC events, which need a machine-readable
+	/// representation instead of matching on `Display` strings. Once assigned, a code
+	/// must never be reused or renumbered; add new codes instead.
+	pub fn as_rpc_code(&self) -> i32 {
+		match self {
+			Error::AlreadyImported(_) => 1001,
+			Error::TemporarilyBanned { .. } => 1002,
+			Error::TooLowPriority { .. } => 1003,
+			Error::CycleDetected => 1004,
+			Error::ImmediatelyDropped => 1005,
+			Error::Unactionable => 1006,
+			Error::InvalidBlockId(_) => 1007,
+			Error::RejectedFutureTransaction => 1008,
+			Error::NoTagsProvided => 1009,
+			// `InvalidTransaction` occupies 1100..=1109 for its named variants and
+			// 1200..=1455 for `Custom`; `UnknownTransaction` is based at 1500 precisely
+			// so its own 1600..=1855 `Custom` range can never overlap the above.
+			Error::InvalidTransaction(invalid) => 1100 + invalid_transaction_code(invalid),
+			Error::UnknownTransaction(unknown) => 1500 + unknown_transaction_code(unknown),
+		}
+	}
+
+	/// Build the serializable RPC representation of this error.
+	///
+	/// `data` carries extra structured context where it exists, e.g. the old/new
+	/// priority for [`Error::TooLowPriority`] or the remaining ban duration for
+	/// [`Error::TemporarilyBanned`], so the RPC layer (and a resubmitting client on
+	/// the other end of the wire) doesn't have to recover it by matching on the
+	/// `Display` string.
+	pub fn to_rpc_error(&self) -> PoolRpcError {
+		let data = match self {
+			Error::TooLowPriority { old, new } =>
+				Some(serde_json::json!({ "old": old, "new": new })),
+			Error::TemporarilyBanned { until } => Some(serde_json::json!({
+				"retry_after_secs": until.saturating_duration_since(Instant::now()).as_secs(),
+			})),
+			_ => None,
+		};
+		PoolRpcError { code: self.as_rpc_code(), message: self.to_string(), data }
+	}
+
+	/// Classify this error for the block authorship iterator.
+	///
+	/// Authorship walks ready transactions in priority order and needs a single,
+	/// authoritative answer for each error it hits: drop the transaction and move on
+	/// (`Skip`), leave it in the pool for a later block (`Defer`), or stop building the
+	/// block altogether (`Abort`).
+	pub fn authorship_action(&self) -> AuthorshipAction {
+		use AuthorshipAction::{Abort, Defer, Skip};
 		match self {
-			// An invalid transaction is temporarily banned, however it can
-			// become valid at a later time.
-			Error::TemporarilyBanned |
-			// The pool is full at the moment.
-			Error::ImmediatelyDropped |
-			// The block id is not known to the pool.
-			// The node might be lagging behind, or during a warp sync.
-			Error::InvalidBlockId(_) |
-			// The pool is configured to not accept future transactions.
-			Error::RejectedFutureTransaction => {
-				true
-			}
-			_ => false
+			Error::InvalidBlockId(_) => Abort,
+			Error::TooLowPriority { .. } | Error::ImmediatelyDropped => Defer,
+			Error::InvalidTransaction(InvalidTransaction::ExhaustsResources) => Defer,
+			_ => Skip,
 		}
 	}
 }
 
+/// The outcome the block authorship iterator should apply for a ready transaction that
+/// failed with a given [`Error`]. See [`Error::authorship_action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorshipAction {
+	/// The transaction is invalid at the current block; drop it and move on to the
+	/// next ready transaction.
+	Skip,
+	/// The transaction is still valid but doesn't fit in the block being built; leave
+	/// it in the pool so it can be tried again for a later block.
+	Defer,
+	/// A fatal pool or state error; stop building the block.
+	Abort,
+}
+
+/// The stable sub-code of an [`InvalidTransaction`], added on top of the 1100 base.
+fn invalid_transaction_code(invalid: &InvalidTransaction) -> i32 {
+	match invalid {
+		InvalidTransaction::Call => 0,
+		InvalidTransaction::Payment => 1,
+		InvalidTransaction::Future => 2,
+		InvalidTransaction::Stale => 3,
+		InvalidTransaction::BadProof => 4,
+		InvalidTransaction::AncientBirthBlock => 5,
+		InvalidTransaction::ExhaustsResources => 6,
+		InvalidTransaction::BadMandatory => 7,
+		InvalidTransaction::MandatoryValidation => 8,
+		InvalidTransaction::BadSigner => 9,
+		InvalidTransaction::Custom(code) => 100 + *code as i32,
+	}
+}
+
+/// The stable sub-code of an [`UnknownTransaction`], added on top of the 1500 base.
+fn unknown_transaction_code(unknown: &UnknownTransaction) -> i32 {
+	match unknown {
+		UnknownTransaction::CannotLookup => 0,
+		UnknownTransaction::NoUnsignedValidator => 1,
+		UnknownTransaction::Custom(code) => 100 + *code as i32,
+	}
+}
+
+/// Serializable, machine-readable representation of an [`Error`].
+///
+/// Produced by [`Error::to_rpc_error`] to back RPC event payloads such as
+/// `transactionWatch`'s `Invalid`/`Dropped` events.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PoolRpcError {
+	/// Stable numeric error code, see [`Error::as_rpc_code`].
+	pub code: i32,
+	/// Human-readable message, same text as the error's `Display` impl.
+	pub message: String,
+	/// Extra structured context, e.g. the old/new priority for a priority clash.
+	pub data: Option<serde_json::Value>,
+}
+
 /// Transaction pool error conversion.
 pub trait IntoPoolError: std::error::Error + Send + Sized + Sync {
 	/// Try to extract original `Error`
@@ -112,3 +387,169 @@ impl IntoPoolError for Error {
 		Ok(self)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Every code assigned here is part of the RPC wire format: changing one is a
+	// breaking change for clients, so lock them down explicitly.
+	#[test]
+	fn rpc_codes_are_locked() {
+		assert_eq!(Error::TemporarilyBanned { until: Instant::now() }.as_rpc_code(), 1002);
+		assert_eq!(Error::CycleDetected.as_rpc_code(), 1004);
+		assert_eq!(Error::ImmediatelyDropped.as_rpc_code(), 1005);
+		assert_eq!(Error::NoTagsProvided.as_rpc_code(), 1009);
+		assert_eq!(
+			Error::InvalidTransaction(InvalidTransaction::Payment).as_rpc_code(),
+			1101
+		);
+		assert_eq!(
+			Error::InvalidTransaction(InvalidTransaction::Custom(7)).as_rpc_code(),
+			1207
+		);
+		assert_eq!(
+			Error::UnknownTransaction(UnknownTransaction::NoUnsignedValidator).as_rpc_code(),
+			1501
+		);
+	}
+
+	#[test]
+	fn rpc_codes_are_unique_across_wrapped_custom_ranges() {
+		let mut codes = std::collections::HashSet::new();
+		let mut insert = |code: i32| assert!(codes.insert(code), "duplicate RPC code: {code}");
+
+		insert(Error::AlreadyImported(Box::new(())).as_rpc_code());
+		insert(Error::TemporarilyBanned { until: Instant::now() }.as_rpc_code());
+		insert(Error::TooLowPriority { old: 1, new: 2 }.as_rpc_code());
+		insert(Error::CycleDetected.as_rpc_code());
+		insert(Error::ImmediatelyDropped.as_rpc_code());
+		insert(Error::Unactionable.as_rpc_code());
+		insert(Error::InvalidBlockId(String::new()).as_rpc_code());
+		insert(Error::RejectedFutureTransaction.as_rpc_code());
+		insert(Error::NoTagsProvided.as_rpc_code());
+
+		for invalid in [
+			InvalidTransaction::Call,
+			InvalidTransaction::Payment,
+			InvalidTransaction::Future,
+			InvalidTransaction::Stale,
+			InvalidTransaction::BadProof,
+			InvalidTransaction::AncientBirthBlock,
+			InvalidTransaction::ExhaustsResources,
+			InvalidTransaction::BadMandatory,
+			InvalidTransaction::MandatoryValidation,
+			InvalidTransaction::BadSigner,
+		] {
+			insert(Error::InvalidTransaction(invalid).as_rpc_code());
+		}
+		for unknown in [UnknownTransaction::CannotLookup, UnknownTransaction::NoUnsignedValidator] {
+			insert(Error::UnknownTransaction(unknown).as_rpc_code());
+		}
+
+		// The two `Custom(u8)` sub-ranges are where the collision actually happened:
+		// every code across the full `u8` range must still be unique between them.
+		for code in 0..=u8::MAX {
+			insert(Error::InvalidTransaction(InvalidTransaction::Custom(code)).as_rpc_code());
+			insert(Error::UnknownTransaction(UnknownTransaction::Custom(code)).as_rpc_code());
+		}
+	}
+
+	#[test]
+	fn to_rpc_error_round_trips_through_json() {
+		let err = Error::TooLowPriority { old: 5, new: 1 };
+		let rpc_error = err.to_rpc_error();
+		let value = serde_json::to_value(&rpc_error).expect("PoolRpcError serializes");
+
+		assert_eq!(value["code"], 1003);
+		assert_eq!(value["message"], err.to_string());
+		assert_eq!(value["data"]["old"], 5);
+		assert_eq!(value["data"]["new"], 1);
+	}
+
+	#[test]
+	fn to_rpc_error_has_no_data_when_error_carries_none() {
+		let rpc_error = Error::CycleDetected.to_rpc_error();
+		assert_eq!(rpc_error.data, None);
+	}
+
+	#[test]
+	fn to_rpc_error_carries_ban_deadline_for_temporarily_banned() {
+		let err = Error::TemporarilyBanned { until: Instant::now() + Duration::from_secs(10) };
+		let rpc_error = err.to_rpc_error();
+		let value = serde_json::to_value(&rpc_error).expect("PoolRpcError serializes");
+
+		assert_eq!(value["code"], 1002);
+		assert_eq!(value["message"], err.to_string());
+		let retry_after_secs = value["data"]["retry_after_secs"].as_u64().unwrap();
+		assert!(retry_after_secs <= 10);
+	}
+
+	#[test]
+	fn retry_after_reports_exact_ban_deadline() {
+		let until = Instant::now() + Duration::from_secs(10);
+		let retry_after = Error::TemporarilyBanned { until }.retry_after().unwrap();
+
+		assert!(retry_after <= Duration::from_secs(10));
+		assert!(retry_after > Duration::from_secs(0));
+	}
+
+	#[test]
+	fn retry_after_is_none_for_unrecoverable_errors() {
+		assert_eq!(Error::CycleDetected.retry_after(), None);
+	}
+
+	#[test]
+	fn is_retriable_preserves_too_low_priority_semantics() {
+		assert!(!Error::TooLowPriority { old: 5, new: 1 }.is_retriable());
+	}
+
+	#[test]
+	fn authorship_action_skips_invalid_at_current_block() {
+		assert_eq!(
+			Error::InvalidTransaction(InvalidTransaction::Stale).authorship_action(),
+			AuthorshipAction::Skip
+		);
+		assert_eq!(
+			Error::InvalidTransaction(InvalidTransaction::BadProof).authorship_action(),
+			AuthorshipAction::Skip
+		);
+		assert_eq!(Error::CycleDetected.authorship_action(), AuthorshipAction::Skip);
+	}
+
+	#[test]
+	fn authorship_action_defers_transactions_that_merely_dont_fit() {
+		assert_eq!(
+			Error::InvalidTransaction(InvalidTransaction::ExhaustsResources).authorship_action(),
+			AuthorshipAction::Defer
+		);
+		assert_eq!(
+			Error::TooLowPriority { old: 1, new: 2 }.authorship_action(),
+			AuthorshipAction::Defer
+		);
+		assert_eq!(Error::ImmediatelyDropped.authorship_action(), AuthorshipAction::Defer);
+	}
+
+	#[test]
+	fn authorship_action_aborts_on_fatal_pool_errors() {
+		assert_eq!(
+			Error::InvalidBlockId("gone".into()).authorship_action(),
+			AuthorshipAction::Abort
+		);
+	}
+
+	#[test]
+	fn permanent_invalid_transaction_causes_get_distinct_reasons() {
+		let reason_of = |invalid| match Error::InvalidTransaction(invalid).recoverability() {
+			Recoverability::Unrecoverable(reason) => reason,
+			Recoverability::Recoverable(reason) => panic!("expected unrecoverable, got {reason:?}"),
+		};
+
+		assert_eq!(reason_of(InvalidTransaction::BadProof), Reason::BadProof);
+		assert_eq!(reason_of(InvalidTransaction::Call), Reason::BadCall);
+		assert_eq!(reason_of(InvalidTransaction::AncientBirthBlock), Reason::AncientBirthBlock);
+		assert_eq!(reason_of(InvalidTransaction::BadMandatory), Reason::BadMandatory);
+		assert_eq!(reason_of(InvalidTransaction::MandatoryValidation), Reason::MandatoryValidation);
+		assert_eq!(reason_of(InvalidTransaction::BadSigner), Reason::BadSigner);
+	}
+}